@@ -0,0 +1,402 @@
+extern crate byteorder;
+
+use self::byteorder::{NetworkEndian, ReadBytesExt, WriteBytesExt};
+use std::io::Write;
+
+use crate::deserialize::{self, FromSql};
+use crate::pg::Pg;
+use crate::serialize::{self, IsNull, Output, ToSql};
+use crate::sql_types::{Array, HasSqlType, Nullable};
+
+/// Writes the common Postgres array header (`ndim`, `has_null`, element oid,
+/// and one `(length, lower_bound)` pair per dimension), then hands off to
+/// `write_element` for each value in row-major order.
+///
+/// `element_oid` must be the real OID of the array's element type:
+/// `array_recv` on the backend compares it against the OID it already
+/// expects for the column/bind parameter, and rejects the value outright
+/// on a mismatch (including the placeholder OID `0`).
+fn write_array<W, T, F>(
+    out: &mut Output<W, Pg>,
+    element_oid: u32,
+    dimensions: &[i32],
+    has_null: bool,
+    elements: &[T],
+    mut write_element: F,
+) -> serialize::Result
+where
+    W: Write,
+    F: FnMut(&T, &mut Output<W, Pg>) -> serialize::Result,
+{
+    out.write_i32::<NetworkEndian>(dimensions.len() as i32)?;
+    out.write_i32::<NetworkEndian>(has_null as i32)?;
+    out.write_u32::<NetworkEndian>(element_oid)?;
+
+    for &length in dimensions {
+        out.write_i32::<NetworkEndian>(length)?;
+        // Postgres arrays are one-indexed by convention.
+        out.write_i32::<NetworkEndian>(1)?;
+    }
+
+    for element in elements {
+        write_element(element, out)?;
+    }
+
+    Ok(IsNull::No)
+}
+
+/// Looks up the element type's real OID through the same
+/// `HasSqlType`/metadata-lookup path every other `ToSql` impl uses to
+/// resolve its type, instead of a hard-coded placeholder.
+fn element_oid<ST, W>(out: &Output<W, Pg>) -> deserialize::Result<u32>
+where
+    Pg: HasSqlType<ST>,
+    W: Write,
+{
+    Ok(Pg::metadata(out.metadata_lookup()).oid()?)
+}
+
+fn write_sized_element<W, T, ST>(value: &T, out: &mut Output<W, Pg>) -> serialize::Result
+where
+    W: Write,
+    T: ToSql<ST, Pg>,
+{
+    let mut buffer = Output::new(Vec::new(), out.metadata_lookup());
+    let is_null = value.to_sql(&mut buffer)?;
+    match is_null {
+        IsNull::Yes => out.write_i32::<NetworkEndian>(-1)?,
+        IsNull::No => {
+            let bytes = buffer.into_inner();
+            out.write_i32::<NetworkEndian>(bytes.len() as i32)?;
+            out.write_all(&bytes)?;
+        }
+    }
+    Ok(())
+}
+
+/// The parsed form of a Postgres array's binary wire format, prior to being
+/// grouped into a Rust `Vec`/`Vec<Vec<_>>`.
+///
+/// `pub(crate)` so the [`kind`](super::kind) module can reuse the same
+/// parsing for the `Array` arm of `PgValue::from_sql_dynamic`, instead of
+/// duplicating the wire format here and there.
+pub(crate) struct RawArray<'a> {
+    pub(crate) dimensions: Vec<i32>,
+    pub(crate) has_null: bool,
+    pub(crate) elements: Vec<Option<&'a [u8]>>,
+}
+
+pub(crate) fn read_array(mut bytes: &[u8]) -> deserialize::Result<RawArray<'_>> {
+    let ndim = bytes.read_i32::<NetworkEndian>()?;
+    let has_null = bytes.read_i32::<NetworkEndian>()? != 0;
+    let _element_oid = bytes.read_i32::<NetworkEndian>()?;
+
+    let mut dimensions = Vec::with_capacity(ndim as usize);
+    for _ in 0..ndim {
+        let length = bytes.read_i32::<NetworkEndian>()?;
+        let _lower_bound = bytes.read_i32::<NetworkEndian>()?;
+        dimensions.push(length);
+    }
+
+    // `Iterator::product` on an empty iterator returns `1` (the
+    // multiplicative identity), but an empty `dimensions` list means
+    // `ndim == 0`, which is how Postgres represents an empty array
+    // (e.g. `'{}'::int4[]`) -- that has zero elements, not one.
+    let total_elements = if dimensions.is_empty() {
+        0
+    } else {
+        dimensions.iter().product::<i32>().max(0) as usize
+    };
+    let mut elements = Vec::with_capacity(total_elements);
+    for _ in 0..total_elements {
+        let len = bytes.read_i32::<NetworkEndian>()?;
+        if len == -1 {
+            elements.push(None);
+        } else {
+            let (value, rest) = bytes.split_at(len as usize);
+            elements.push(Some(value));
+            bytes = rest;
+        }
+    }
+
+    Ok(RawArray {
+        dimensions,
+        has_null,
+        elements,
+    })
+}
+
+fn expect_dimensions(raw: &RawArray<'_>, expected: usize) -> deserialize::Result<()> {
+    if !raw.dimensions.is_empty() && raw.dimensions.len() != expected {
+        return Err(format!(
+            "Cannot deserialize a {}-dimension Postgres array as a {}-dimension Rust type",
+            raw.dimensions.len(),
+            expected,
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn expect_no_null(raw: &RawArray<'_>) -> deserialize::Result<()> {
+    if raw.has_null {
+        return Err(
+            "Unexpected NULL element in Postgres array for a non-nullable element type".into(),
+        );
+    }
+    Ok(())
+}
+
+impl<T, ST> ToSql<Array<ST>, Pg> for Vec<T>
+where
+    T: ToSql<ST, Pg>,
+    Pg: HasSqlType<ST>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        ToSql::<Array<ST>, Pg>::to_sql(&&self[..], out)
+    }
+}
+
+impl<'a, T, ST> ToSql<Array<ST>, Pg> for &'a [T]
+where
+    T: ToSql<ST, Pg>,
+    Pg: HasSqlType<ST>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let oid = element_oid::<ST, _>(out)?;
+        write_array(out, oid, &[self.len() as i32], false, self, |value, out| {
+            write_sized_element::<_, _, ST>(value, out)
+        })
+    }
+}
+
+impl<T, ST> FromSql<Array<ST>, Pg> for Vec<T>
+where
+    T: FromSql<ST, Pg>,
+{
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let raw = read_array(not_none!(bytes))?;
+        expect_dimensions(&raw, 1)?;
+        expect_no_null(&raw)?;
+        raw.elements.into_iter().map(T::from_sql).collect()
+    }
+}
+
+impl<T, ST> ToSql<Array<Nullable<ST>>, Pg> for Vec<Option<T>>
+where
+    T: ToSql<ST, Pg>,
+    Pg: HasSqlType<ST>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        ToSql::<Array<Nullable<ST>>, Pg>::to_sql(&&self[..], out)
+    }
+}
+
+impl<'a, T, ST> ToSql<Array<Nullable<ST>>, Pg> for &'a [Option<T>]
+where
+    T: ToSql<ST, Pg>,
+    Pg: HasSqlType<ST>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let oid = element_oid::<ST, _>(out)?;
+        let has_null = self.iter().any(Option::is_none);
+        write_array(
+            out,
+            oid,
+            &[self.len() as i32],
+            has_null,
+            self,
+            |value, out| match value {
+                Some(value) => write_sized_element::<_, _, ST>(value, out),
+                None => {
+                    out.write_i32::<NetworkEndian>(-1)?;
+                    Ok(())
+                }
+            },
+        )
+    }
+}
+
+impl<T, ST> FromSql<Array<Nullable<ST>>, Pg> for Vec<Option<T>>
+where
+    T: FromSql<ST, Pg>,
+{
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let raw = read_array(not_none!(bytes))?;
+        expect_dimensions(&raw, 1)?;
+        raw.elements
+            .into_iter()
+            .map(|element| element.map(T::from_sql).transpose())
+            .collect()
+    }
+}
+
+impl<T, ST> ToSql<Array<ST>, Pg> for Vec<Vec<T>>
+where
+    T: ToSql<ST, Pg>,
+    Pg: HasSqlType<ST>,
+{
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let oid = element_oid::<ST, _>(out)?;
+        let inner_len = self.first().map_or(0, Vec::len);
+        if self.iter().any(|row| row.len() != inner_len) {
+            return Err("Postgres arrays must be rectangular; rows had different lengths".into());
+        }
+        let flattened = self.iter().flatten().collect::<Vec<_>>();
+        write_array(
+            out,
+            oid,
+            &[self.len() as i32, inner_len as i32],
+            false,
+            &flattened,
+            |value, out| write_sized_element::<_, _, ST>(*value, out),
+        )
+    }
+}
+
+impl<T, ST> FromSql<Array<ST>, Pg> for Vec<Vec<T>>
+where
+    T: FromSql<ST, Pg>,
+{
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let raw = read_array(not_none!(bytes))?;
+        expect_dimensions(&raw, 2)?;
+        expect_no_null(&raw)?;
+
+        let outer_len = raw.dimensions.first().copied().unwrap_or(0) as usize;
+        let inner_len = raw.dimensions.get(1).copied().unwrap_or(0) as usize;
+
+        // An empty inner dimension (e.g. `[2, 0]`) still has `outer_len` rows,
+        // each empty -- but `elements` itself is empty, so `chunks` would
+        // yield zero chunks and silently drop the row count.
+        if inner_len == 0 {
+            return Ok(vec![Vec::new(); outer_len]);
+        }
+
+        let elements = raw
+            .elements
+            .into_iter()
+            .map(T::from_sql)
+            .collect::<deserialize::Result<Vec<_>>>()?;
+        Ok(elements.chunks(inner_len).map(<[T]>::to_vec).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_types::Integer;
+
+    fn write(dimensions: &[i32], has_null: bool, elements: &[Option<[u8; 4]>]) -> Vec<u8> {
+        let mut out = Output::test(Vec::new());
+        write_array(
+            &mut out,
+            23,
+            dimensions,
+            has_null,
+            elements,
+            |element, out| {
+                match element {
+                    Some(bytes) => {
+                        out.write_i32::<NetworkEndian>(bytes.len() as i32)?;
+                        out.write_all(bytes)?;
+                    }
+                    None => out.write_i32::<NetworkEndian>(-1)?,
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+        out.into_inner()
+    }
+
+    fn elem(n: i32) -> Option<[u8; 4]> {
+        Some(n.to_be_bytes())
+    }
+
+    #[test]
+    fn empty_array_has_zero_elements() {
+        let bytes = write(&[], false, &[]);
+        let raw = read_array(&bytes).unwrap();
+        assert!(raw.dimensions.is_empty());
+        assert!(raw.elements.is_empty());
+    }
+
+    #[test]
+    fn from_sql_accepts_an_empty_array() {
+        let bytes = write(&[], false, &[]);
+        let result = <Vec<i32> as FromSql<Array<Integer>, Pg>>::from_sql(Some(&bytes)).unwrap();
+        assert_eq!(Vec::<i32>::new(), result);
+    }
+
+    #[test]
+    fn one_dimensional_non_null_array_round_trips() {
+        let bytes = write(&[2], false, &[elem(1), elem(2)]);
+        let raw = read_array(&bytes).unwrap();
+        assert_eq!(vec![2], raw.dimensions);
+        assert!(!raw.has_null);
+        assert_eq!(
+            vec![Some(&1i32.to_be_bytes()[..]), Some(&2i32.to_be_bytes()[..])],
+            raw.elements
+        );
+
+        let result = <Vec<i32> as FromSql<Array<Integer>, Pg>>::from_sql(Some(&bytes)).unwrap();
+        assert_eq!(vec![1, 2], result);
+    }
+
+    #[test]
+    fn one_dimensional_array_with_null_round_trips() {
+        let bytes = write(&[2], true, &[elem(1), None]);
+        let raw = read_array(&bytes).unwrap();
+        assert!(raw.has_null);
+        assert_eq!(vec![Some(&1i32.to_be_bytes()[..]), None], raw.elements);
+
+        let result =
+            <Vec<Option<i32>> as FromSql<Array<Nullable<Integer>>, Pg>>::from_sql(Some(&bytes))
+                .unwrap();
+        assert_eq!(vec![Some(1), None], result);
+    }
+
+    #[test]
+    fn two_dimensional_array_round_trips() {
+        let elements = [elem(1), elem(2), elem(3), elem(4), elem(5), elem(6)];
+        let bytes = write(&[2, 3], false, &elements);
+        let raw = read_array(&bytes).unwrap();
+        assert_eq!(vec![2, 3], raw.dimensions);
+
+        let result =
+            <Vec<Vec<i32>> as FromSql<Array<Integer>, Pg>>::from_sql(Some(&bytes)).unwrap();
+        assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], result);
+    }
+
+    #[test]
+    fn two_dimensional_array_with_empty_rows_keeps_its_row_count() {
+        let bytes = write(&[2, 0], false, &[]);
+        let result =
+            <Vec<Vec<i32>> as FromSql<Array<Integer>, Pg>>::from_sql(Some(&bytes)).unwrap();
+        assert_eq!(vec![Vec::<i32>::new(), Vec::new()], result);
+    }
+
+    #[test]
+    fn nullable_slice_does_not_require_element_clone_to_serialize() {
+        #[derive(Debug, PartialEq)]
+        struct NotClone(i32);
+
+        impl ToSql<Integer, Pg> for NotClone {
+            fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+                ToSql::<Integer, Pg>::to_sql(&self.0, out)
+            }
+        }
+
+        let values = [Some(NotClone(1)), None];
+        let slice: &[Option<NotClone>] = &values;
+        let mut out = Output::test(Vec::new());
+        ToSql::<Array<Nullable<Integer>>, Pg>::to_sql(&slice, &mut out).unwrap();
+        let bytes = out.into_inner();
+
+        let result =
+            <Vec<Option<i32>> as FromSql<Array<Nullable<Integer>>, Pg>>::from_sql(Some(&bytes))
+                .unwrap();
+        assert_eq!(vec![Some(1), None], result);
+    }
+}
@@ -0,0 +1,89 @@
+extern crate bit_vec;
+extern crate byteorder;
+
+use self::bit_vec::BitVec;
+use self::byteorder::{NetworkEndian, ReadBytesExt, WriteBytesExt};
+use std::io::Write;
+
+use crate::deserialize::{self, FromSql};
+use crate::pg::Pg;
+use crate::serialize::{self, IsNull, Output, ToSql};
+use crate::sql_types::{Bit, Varbit};
+
+macro_rules! bit_vec_impls {
+    ($sql_type:ty) => {
+        impl ToSql<$sql_type, Pg> for BitVec {
+            fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+                out.write_i32::<NetworkEndian>(self.len() as i32)?;
+                for byte in bits_to_bytes(self) {
+                    out.write_u8(byte)?;
+                }
+                Ok(IsNull::No)
+            }
+        }
+
+        impl FromSql<$sql_type, Pg> for BitVec {
+            fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+                let mut bytes = not_none!(bytes);
+                let len = bytes.read_i32::<NetworkEndian>()?;
+                if bytes.len() != ((len as usize) + 7) / 8 {
+                    return Err("Invalid data bytes for BitVec".into());
+                }
+                Ok(bytes_to_bits(bytes, len as usize))
+            }
+        }
+    };
+}
+
+bit_vec_impls!(Bit);
+bit_vec_impls!(Varbit);
+
+/// Packs `bits` into bytes, most-significant bit of the first byte first,
+/// zero-padding the final byte to a whole number of bytes.
+fn bits_to_bytes(bits: &BitVec) -> Vec<u8> {
+    let mut bytes = vec![0u8; (bits.len() + 7) / 8];
+    for (i, bit) in bits.iter().enumerate() {
+        if bit {
+            bytes[i / 8] |= 0b1000_0000 >> (i % 8);
+        }
+    }
+    bytes
+}
+
+/// The inverse of [`bits_to_bytes`]: reads the first `len` bits out of
+/// `bytes`, ignoring any padding bits in the final byte.
+fn bytes_to_bits(bytes: &[u8], len: usize) -> BitVec {
+    let mut bits = BitVec::from_elem(len, false);
+    for i in 0..len {
+        let byte = bytes[i / 8];
+        let bit = byte & (0b1000_0000 >> (i % 8)) != 0;
+        bits.set(i, bit);
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bits_to_bytes, bytes_to_bits};
+    use bit_vec::BitVec;
+
+    #[test]
+    fn round_trips_through_the_wire_format() {
+        let mut bits = BitVec::from_elem(12, false);
+        bits.set(0, true);
+        bits.set(1, true);
+        bits.set(11, true);
+
+        let bytes = bits_to_bytes(&bits);
+        let round_tripped = bytes_to_bits(&bytes, bits.len());
+
+        assert_eq!(bits, round_tripped);
+    }
+
+    #[test]
+    fn padding_bits_are_zeroed() {
+        let bits = BitVec::from_elem(4, true);
+        let bytes = bits_to_bytes(&bits);
+        assert_eq!(vec![0b1111_0000], bytes);
+    }
+}
@@ -0,0 +1,150 @@
+extern crate byteorder;
+
+use self::byteorder::{NetworkEndian, ReadBytesExt, WriteBytesExt};
+use std::io::Write;
+
+use crate::deserialize::{self, FromSql};
+use crate::pg::Pg;
+use crate::serialize::{self, IsNull, Output, ToSql};
+use crate::sql_types::Timetz;
+
+/// Maps `(chrono::NaiveTime, chrono::FixedOffset)` to and from [`PgTimeTz`]
+#[cfg(feature = "chrono")]
+mod chrono {
+    extern crate chrono;
+
+    use self::chrono::{FixedOffset, NaiveTime};
+
+    use super::PgTimeTz;
+    use crate::deserialize::{self, FromSql};
+    use crate::pg::Pg;
+    use crate::serialize::{self, Output, ToSql};
+    use crate::sql_types::Timetz;
+    use std::io::Write;
+
+    impl ToSql<Timetz, Pg> for (NaiveTime, FixedOffset) {
+        fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+            let (time, offset) = *self;
+            ToSql::<Timetz, Pg>::to_sql(&PgTimeTz::from((time, offset)), out)
+        }
+    }
+
+    impl FromSql<Timetz, Pg> for (NaiveTime, FixedOffset) {
+        fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+            let pg_time_tz: PgTimeTz = FromSql::<Timetz, Pg>::from_sql(bytes)?;
+            Ok(pg_time_tz.into())
+        }
+    }
+
+    impl From<(NaiveTime, FixedOffset)> for PgTimeTz {
+        fn from((time, offset): (NaiveTime, FixedOffset)) -> Self {
+            let midnight = NaiveTime::from_hms(0, 0, 0);
+            let time = (time - midnight)
+                .num_microseconds()
+                .expect("Could not represent as microseconds");
+            PgTimeTz {
+                microseconds_since_midnight: time,
+                // PostgreSQL stores the offset as "seconds west of UTC", chrono
+                // stores it as "seconds east of UTC"
+                utc_offset_seconds: -offset.local_minus_utc(),
+            }
+        }
+    }
+
+    impl From<PgTimeTz> for (NaiveTime, FixedOffset) {
+        fn from(
+            PgTimeTz {
+                microseconds_since_midnight,
+                utc_offset_seconds,
+            }: PgTimeTz,
+        ) -> Self {
+            let time = NaiveTime::from_hms(0, 0, 0)
+                + chrono::Duration::microseconds(microseconds_since_midnight);
+            let offset = FixedOffset::west(utc_offset_seconds);
+            (time, offset)
+        }
+    }
+}
+
+/// Represents the Postgres time with time zone type.
+///
+/// ### [`ToSql`] impl
+///
+/// - This struct
+///
+/// ### [`FromSql`] impl
+///
+/// - This struct
+///
+/// Normally you should prefer to use `chrono::NaiveTime` and
+/// `chrono::FixedOffset` with `feature = "chrono"` to interact with this SQL
+/// type instead, as they interoperate with more of the ecosystem.
+///
+/// [`ToSql`]: ../../serialize/trait.ToSql.html
+/// [`FromSql`]: ../../deserialize/trait.FromSql.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[sql_type = "Timetz"]
+pub struct PgTimeTz {
+    /// The time of day, in microseconds since midnight
+    pub microseconds_since_midnight: i64,
+    /// The zone offset, in seconds west of UTC
+    pub utc_offset_seconds: i32,
+}
+
+impl ToSql<Timetz, Pg> for PgTimeTz {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        out.write_i64::<NetworkEndian>(self.microseconds_since_midnight)?;
+        out.write_i32::<NetworkEndian>(self.utc_offset_seconds)?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Timetz, Pg> for PgTimeTz {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let mut bytes = not_none!(bytes);
+        let microseconds_since_midnight = bytes.read_i64::<NetworkEndian>()?;
+        let utc_offset_seconds = bytes.read_i32::<NetworkEndian>()?;
+        if !bytes.is_empty() {
+            return Err("Invalid data bytes for PgTimeTz".into());
+        }
+        Ok(PgTimeTz {
+            microseconds_since_midnight,
+            utc_offset_seconds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PgTimeTz;
+    use crate::deserialize::FromSql;
+    use crate::pg::Pg;
+    use crate::serialize::{Output, ToSql};
+    use crate::sql_types::Timetz;
+
+    #[test]
+    fn pg_time_tz_round_trips_through_the_wire_format() {
+        let value = PgTimeTz {
+            microseconds_since_midnight: 3_600_000_000,
+            utc_offset_seconds: -18_000,
+        };
+
+        let mut out = Output::test(Vec::new());
+        ToSql::<Timetz, Pg>::to_sql(&value, &mut out).unwrap();
+        let bytes = out.into_inner();
+
+        assert_eq!(
+            3_600_000_000i64.to_be_bytes(),
+            bytes[..8],
+            "microseconds_since_midnight must be written big-endian first"
+        );
+        assert_eq!(
+            (-18_000i32).to_be_bytes(),
+            bytes[8..12],
+            "utc_offset_seconds must follow as a big-endian i32"
+        );
+
+        let round_tripped = <PgTimeTz as FromSql<Timetz, Pg>>::from_sql(Some(&bytes)).unwrap();
+        assert_eq!(value, round_tripped);
+    }
+}
@@ -0,0 +1,281 @@
+//! Support for mapping Rust enums onto user-defined PostgreSQL `ENUM`
+//! types.
+//!
+//! [`super::record`] already covers composite types; `ENUM` is the other
+//! user-defined PostgreSQL type family (`postgres-types` models it as
+//! `Kind::Enum`, see [`super::kind::Kind::Enum`]). A Postgres enum such as
+//! `CREATE TYPE mood AS ENUM ('happy', 'sad')` is transmitted as the
+//! variant's label, so `ToSql` only ever needs to write that label and
+//! `FromSql` only needs to match it back against the variant list.
+//!
+//! Because an enum's OID is assigned per-database at `CREATE TYPE` time
+//! rather than being fixed across installations, the generated SQL type
+//! cannot carry a `#[postgres(oid = ..., array_oid = ...)]` attribute the
+//! way the built-in types in this module do. Instead it carries its type
+//! name (and its array type's name) through [`PgEnumTypeName`].
+//!
+//! This module supplies the runtime plumbing -- [`PgEnumTypeName`],
+//! [`PgEnumValue`], and the [`to_sql`]/[`from_sql`] functions a `ToSql`/
+//! `FromSql` impl calls into -- plus [`db_enum!`], which generates the SQL
+//! type marker and those impls for a given Rust enum:
+//!
+//! ```rust,ignore
+//! # include!("../../doctest_setup.rs");
+//! diesel::db_enum! {
+//!     #[derive(Debug, PartialEq)]
+//!     pub enum Mood {
+//!         Happy = "happy",
+//!         Sad = "sad",
+//!     }
+//!     marker MoodMapping = "mood";
+//! }
+//!
+//! table! {
+//!     use diesel::sql_types::Integer;
+//!     use super::MoodMapping;
+//!
+//!     users {
+//!         id -> Integer,
+//!         mood -> MoodMapping,
+//!     }
+//! }
+//! ```
+//!
+//! `db_enum!` is a `macro_rules!` macro rather than a `#[derive(DbEnum)]`
+//! proc macro: a derive needs its own proc-macro crate, which this
+//! single-crate tree has no room for, but the generated code -- the
+//! `MoodMapping` marker, `impl PgEnumTypeName for MoodMapping`,
+//! `impl PgEnumValue for Mood`, and `ToSql`/`FromSql` for `Mood` -- is the
+//! same either way. [`PgEnumTypeName::type_name`] and
+//! [`PgEnumTypeName::array_type_name`] are only building blocks, though:
+//! `db_enum!` generates no `HasSqlType` impl for the marker, and nothing in
+//! this module looks either name up in `pg_type` to resolve the OID of
+//! `MoodMapping` or `Array<MoodMapping>`.
+
+use std::io::Write;
+
+use crate::deserialize::{self, FromSql};
+use crate::pg::Pg;
+use crate::serialize::{self, IsNull, Output, ToSql};
+use crate::sql_types::SqlType;
+
+/// Implemented for the zero-sized SQL type marker that [`db_enum!`]
+/// generates for each Rust enum.
+///
+/// Unlike the built-in types in this module, a generated enum marker has
+/// no fixed OID to put in a `#[postgres(oid = ...)]` attribute: the OID is
+/// assigned when `CREATE TYPE` runs, and differs between databases (and
+/// even between a database and its test double). `type_name`/`array_type_name`
+/// give the names under which the type and its array type appear in
+/// `pg_type`; this trait does not itself resolve them to OIDs.
+pub trait PgEnumTypeName: SqlType {
+    /// The name the enum was declared with: `CREATE TYPE <name> AS ENUM (..)`.
+    fn type_name() -> &'static str;
+
+    /// The name of the corresponding PostgreSQL array type.
+    ///
+    /// PostgreSQL names a type's array type by prefixing an underscore, so
+    /// the default is derived from [`type_name`](Self::type_name); this is
+    /// only made overridable because it's technically possible (if
+    /// unusual) for a type to be created with an array type under a
+    /// different name.
+    fn array_type_name() -> String {
+        format!("_{}", Self::type_name())
+    }
+}
+
+/// Implemented for the Rust enum itself by [`db_enum!`].
+///
+/// This is intentionally independent of any particular SQL type marker:
+/// the same `Mood` enum could be stored under more than one Postgres enum
+/// type name across different columns, and this trait only needs to know
+/// how to go from a variant to its label and back.
+pub trait PgEnumValue: Sized {
+    /// The text label PostgreSQL stores for this variant.
+    fn label(&self) -> &'static str;
+
+    /// Match a label received from PostgreSQL back to a variant.
+    ///
+    /// Returns `None` for a label the enum doesn't know about, which is
+    /// reported by [`from_sql`] as a descriptive error rather than a
+    /// panic; this can legitimately happen if `ALTER TYPE ... ADD VALUE`
+    /// added a label after the Rust enum was last regenerated.
+    fn from_label(label: &str) -> Option<Self>;
+}
+
+/// Writes `value`'s label as the wire value.
+///
+/// [`db_enum!`] implements `ToSql<SomeGeneratedMarker, Pg>` for the
+/// annotated enum in terms of this function.
+pub fn to_sql<T, W>(value: &T, out: &mut Output<W, Pg>) -> serialize::Result
+where
+    T: PgEnumValue,
+    W: Write,
+{
+    out.write_all(value.label().as_bytes())?;
+    Ok(IsNull::No)
+}
+
+/// Matches the received label against `T`'s variants.
+///
+/// [`db_enum!`] implements `FromSql<SomeGeneratedMarker, Pg>` for the
+/// annotated enum in terms of this function.
+pub fn from_sql<T>(bytes: Option<&[u8]>) -> deserialize::Result<T>
+where
+    T: PgEnumValue,
+{
+    let bytes = not_none!(bytes);
+    let label = std::str::from_utf8(bytes)?;
+    T::from_label(label).ok_or_else(|| {
+        format!(
+            "Unrecognized enum label `{}`; the Rust enum may be out of date with the database",
+            label,
+        )
+        .into()
+    })
+}
+
+/// Generates the plumbing in this module for a Rust enum: the zero-sized
+/// SQL type marker, its [`PgEnumTypeName`]/[`PgEnumValue`] impls, and its
+/// `ToSql`/`FromSql` impls in terms of [`to_sql`]/[`from_sql`].
+///
+/// This plays the role `#[derive(DbEnum)]` would in a full proc-macro
+/// crate, as a `macro_rules!` macro instead: a proc macro needs its own
+/// crate to live in, which this tree has no room for, but the generated
+/// code is the same either way. See the [module docs](self) for a worked
+/// example.
+#[macro_export]
+macro_rules! db_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $label:expr),+ $(,)?
+        }
+        marker $marker:ident = $type_name:expr;
+    ) => {
+        $(#[$meta])*
+        pub enum $name {
+            $($variant),+
+        }
+
+        pub struct $marker;
+
+        impl $crate::sql_types::SqlType for $marker {
+            type IsNull = $crate::sql_types::is_nullable::NotNull;
+        }
+
+        impl $crate::pg::types::enums::PgEnumTypeName for $marker {
+            fn type_name() -> &'static str {
+                $type_name
+            }
+        }
+
+        impl $crate::pg::types::enums::PgEnumValue for $name {
+            fn label(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $label),+
+                }
+            }
+
+            fn from_label(label: &str) -> Option<Self> {
+                match label {
+                    $($label => Some($name::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+
+        impl $crate::serialize::ToSql<$marker, $crate::pg::Pg> for $name {
+            fn to_sql<W: ::std::io::Write>(
+                &self,
+                out: &mut $crate::serialize::Output<W, $crate::pg::Pg>,
+            ) -> $crate::serialize::Result {
+                $crate::pg::types::enums::to_sql(self, out)
+            }
+        }
+
+        impl $crate::deserialize::FromSql<$marker, $crate::pg::Pg> for $name {
+            fn from_sql(bytes: Option<&[u8]>) -> $crate::deserialize::Result<Self> {
+                $crate::pg::types::enums::from_sql(bytes)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Mood {
+        Happy,
+        Sad,
+    }
+
+    impl PgEnumValue for Mood {
+        fn label(&self) -> &'static str {
+            match *self {
+                Mood::Happy => "happy",
+                Mood::Sad => "sad",
+            }
+        }
+
+        fn from_label(label: &str) -> Option<Self> {
+            match label {
+                "happy" => Some(Mood::Happy),
+                "sad" => Some(Mood::Sad),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_label_is_a_descriptive_error() {
+        let error = from_sql::<Mood>(Some(b"furious")).unwrap_err();
+        assert!(error.to_string().contains("furious"));
+    }
+
+    #[test]
+    fn known_label_round_trips() {
+        let value: Mood = from_sql(Some(Mood::Sad.label().as_bytes())).unwrap();
+        assert_eq!(Mood::Sad, value);
+    }
+
+    #[test]
+    fn array_type_name_defaults_to_underscore_prefix() {
+        struct MoodMapping;
+        impl SqlType for MoodMapping {
+            type IsNull = crate::sql_types::is_nullable::NotNull;
+        }
+        impl PgEnumTypeName for MoodMapping {
+            fn type_name() -> &'static str {
+                "mood"
+            }
+        }
+
+        assert_eq!("_mood", MoodMapping::array_type_name());
+    }
+
+    #[test]
+    fn db_enum_macro_generates_working_to_sql_and_from_sql() {
+        db_enum! {
+            #[derive(Debug, PartialEq)]
+            pub enum Weather {
+                Sunny = "sunny",
+                Rainy = "rainy",
+            }
+            marker WeatherMapping = "weather";
+        }
+
+        assert_eq!("weather", WeatherMapping::type_name());
+        assert_eq!("_weather", WeatherMapping::array_type_name());
+
+        let mut out = Output::test(Vec::new());
+        ToSql::<WeatherMapping, Pg>::to_sql(&Weather::Rainy, &mut out).unwrap();
+        let bytes = out.into_inner();
+        assert_eq!(b"rainy", &bytes[..]);
+
+        let value = <Weather as FromSql<WeatherMapping, Pg>>::from_sql(Some(&bytes)).unwrap();
+        assert_eq!(Weather::Rainy, value);
+    }
+}
@@ -0,0 +1,79 @@
+extern crate byteorder;
+
+use self::byteorder::{NetworkEndian, ReadBytesExt, WriteBytesExt};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use crate::deserialize::{self, FromSql};
+use crate::pg::Pg;
+use crate::serialize::{self, IsNull, Output, ToSql};
+use crate::sql_types::Hstore;
+
+fn write_pairs<'a, W, I>(out: &mut Output<W, Pg>, pairs: I) -> serialize::Result
+where
+    W: Write,
+    I: ExactSizeIterator<Item = (&'a str, Option<&'a str>)>,
+{
+    out.write_i32::<NetworkEndian>(pairs.len() as i32)?;
+    for (key, value) in pairs {
+        out.write_i32::<NetworkEndian>(key.len() as i32)?;
+        out.write_all(key.as_bytes())?;
+        match value {
+            Some(value) => {
+                out.write_i32::<NetworkEndian>(value.len() as i32)?;
+                out.write_all(value.as_bytes())?;
+            }
+            None => out.write_i32::<NetworkEndian>(-1)?,
+        }
+    }
+    Ok(IsNull::No)
+}
+
+fn read_pairs(mut bytes: &[u8]) -> deserialize::Result<Vec<(String, Option<String>)>> {
+    let count = bytes.read_i32::<NetworkEndian>()?;
+    let mut pairs = Vec::with_capacity(count.max(0) as usize);
+
+    for _ in 0..count {
+        let key_len = bytes.read_i32::<NetworkEndian>()?;
+        let (key_bytes, rest) = bytes.split_at(key_len as usize);
+        let key = String::from_utf8(key_bytes.to_vec())?;
+        bytes = rest;
+
+        let value_len = bytes.read_i32::<NetworkEndian>()?;
+        let value = if value_len == -1 {
+            None
+        } else {
+            let (value_bytes, rest) = bytes.split_at(value_len as usize);
+            bytes = rest;
+            Some(String::from_utf8(value_bytes.to_vec())?)
+        };
+
+        pairs.push((key, value));
+    }
+
+    Ok(pairs)
+}
+
+impl ToSql<Hstore, Pg> for HashMap<String, Option<String>> {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        write_pairs(out, self.iter().map(|(k, v)| (k.as_str(), v.as_deref())))
+    }
+}
+
+impl FromSql<Hstore, Pg> for HashMap<String, Option<String>> {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        Ok(read_pairs(not_none!(bytes))?.into_iter().collect())
+    }
+}
+
+impl ToSql<Hstore, Pg> for BTreeMap<String, Option<String>> {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        write_pairs(out, self.iter().map(|(k, v)| (k.as_str(), v.as_deref())))
+    }
+}
+
+impl FromSql<Hstore, Pg> for BTreeMap<String, Option<String>> {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        Ok(read_pairs(not_none!(bytes))?.into_iter().collect())
+    }
+}
@@ -0,0 +1,130 @@
+extern crate byteorder;
+
+use self::byteorder::{NetworkEndian, ReadBytesExt, WriteBytesExt};
+use std::io::Write;
+
+use crate::deserialize::{self, FromSql};
+use crate::pg::Pg;
+use crate::serialize::{self, IsNull, Output, ToSql};
+use crate::sql_types::Oid;
+
+/// The existing representation of `Oid`, kept so code written against it
+/// keeps compiling. Prefer [`PgOid`] in new code: a bare `u32` makes it easy
+/// to pass an ordinary integer (a row count, a primary key) anywhere an
+/// object identifier is expected, which is exactly the mix-up that led
+/// peer Postgres clients to replace `u32` with a dedicated `Oid` type
+/// across their APIs.
+impl ToSql<Oid, Pg> for u32 {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        out.write_u32::<NetworkEndian>(*self)?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Oid, Pg> for u32 {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let mut bytes = not_none!(bytes);
+        let value = bytes.read_u32::<NetworkEndian>()?;
+        if !bytes.is_empty() {
+            return Err("Invalid data bytes for u32".into());
+        }
+        Ok(value)
+    }
+}
+
+/// A PostgreSQL object identifier ([`OID`]).
+///
+/// This is a thin, `Copy` newtype around the raw `u32` PostgreSQL uses to
+/// identify catalog rows (tables, types, functions, ...), so that
+/// functions which accept an OID (system-catalog queries, `lo_*` large
+/// object calls, ...) can require `PgOid` and have the compiler reject a
+/// plain integer passed by mistake, rather than silently accepting any
+/// `u32`.
+///
+/// [`OID`]: ../sql_types/struct.Oid.html
+///
+/// # Examples
+///
+/// ```rust
+/// # include!("../../doctest_setup.rs");
+/// use diesel::pg::data_types::PgOid;
+///
+/// table! {
+///     objects (oid) {
+///         oid -> Oid,
+///         relname -> VarChar,
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// #     use diesel::insert_into;
+/// #     use self::objects::dsl::*;
+/// #     let connection = connection_no_data();
+/// #     diesel::sql_query("CREATE TABLE objects (
+/// #         oid OID PRIMARY KEY,
+/// #         relname VARCHAR NOT NULL
+/// #     )").execute(&connection)?;
+/// let inserted_oid = insert_into(objects)
+///     .values((oid.eq(PgOid::from(16384)), relname.eq("widgets")))
+///     .returning(oid)
+///     .get_result::<PgOid>(&connection)?;
+/// assert_eq!(PgOid::from(16384), inserted_oid);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    QueryId,
+    AsExpression,
+    FromSqlRow,
+)]
+#[sql_type = "Oid"]
+pub struct PgOid(pub u32);
+
+impl From<u32> for PgOid {
+    fn from(oid: u32) -> Self {
+        PgOid(oid)
+    }
+}
+
+impl From<PgOid> for u32 {
+    fn from(oid: PgOid) -> Self {
+        oid.0
+    }
+}
+
+impl ToSql<Oid, Pg> for PgOid {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        ToSql::<Oid, Pg>::to_sql(&self.0, out)
+    }
+}
+
+impl FromSql<Oid, Pg> for PgOid {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        <u32 as FromSql<Oid, Pg>>::from_sql(bytes).map(PgOid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PgOid;
+
+    #[test]
+    fn converts_to_and_from_u32() {
+        let oid = PgOid::from(16384);
+        assert_eq!(16384u32, oid.into());
+    }
+
+    #[test]
+    fn orders_like_the_underlying_u32() {
+        assert!(PgOid::from(1) < PgOid::from(2));
+    }
+}
@@ -0,0 +1,436 @@
+//! Runtime inspection of a column's actual PostgreSQL type.
+//!
+//! Everything else in [`super::sql_types`] is a compile-time marker: the
+//! Rust type of a query result is fixed by the `table!`/`sql_query` types
+//! written in the source. That works as long as the schema is known ahead
+//! of time, but tools that run against arbitrary schemas (admin UIs, CSV
+//! exporters, a generic `SELECT *` browser) need to find out what a column
+//! *actually* is once the query has run, and build a value accordingly.
+//!
+//! This module is modeled on the `Kind`/`Type` inspection in the
+//! `postgres-types` ecosystem: every column carries an OID, and [`Kind`]
+//! classifies what that OID means (a plain scalar, an array of some other
+//! OID, a range, a composite type with named fields, an enum with its
+//! labels, or a domain over some base OID). [`PgTypeDescriptor`] bundles an
+//! OID with its `Kind` and is what a [`FromSqlDynamic`] implementation
+//! receives instead of the usual compile-time `ST` type parameter. The
+//! row and field types do not expose descriptors; callers build them with
+//! [`PgTypeDescriptor::new`].
+
+extern crate byteorder;
+
+use self::byteorder::{NetworkEndian, ReadBytesExt};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::array::read_array;
+use crate::deserialize;
+
+/// A classification of what a PostgreSQL OID actually represents.
+///
+/// This is the dynamic counterpart to the compile-time markers in
+/// [`super::sql_types`]: `Simple` corresponds to a plain type like
+/// `Oid`/`Timestamptz`, `Array`/`Range`/`Domain` each wrap the OID of the
+/// type they are built from, `Composite` lists the OID of every field in
+/// declaration order, and `Enum` lists the labels in the order PostgreSQL
+/// assigns them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// An ordinary scalar type, such as `int4` or `timestamptz`.
+    Simple,
+    /// An array, with the OID of its element type.
+    Array(u32),
+    /// A range, with the OID of the type it ranges over.
+    Range(u32),
+    /// A composite (row) type, with the name and OID of each field in
+    /// declaration order.
+    Composite(Vec<(String, u32)>),
+    /// An enum, with its labels in the order PostgreSQL assigns them.
+    Enum(Vec<String>),
+    /// A domain, with the OID of its underlying base type.
+    Domain(u32),
+}
+
+/// The runtime description of a column's actual PostgreSQL type: its OID
+/// and what kind of type that OID is.
+///
+/// A [`PgTypeDescriptor`] is what [`FromSqlDynamic`] receives in place of
+/// a compile-time `ST` type parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgTypeDescriptor {
+    oid: u32,
+    kind: Kind,
+}
+
+impl PgTypeDescriptor {
+    /// Construct a descriptor for a known OID and [`Kind`].
+    ///
+    /// Nothing in this module queries `pg_type`/`pg_attribute`/`pg_enum`;
+    /// the caller supplies the OID and its `Kind`.
+    pub fn new(oid: u32, kind: Kind) -> Self {
+        PgTypeDescriptor { oid, kind }
+    }
+
+    /// The OID of the column as reported by PostgreSQL.
+    pub fn oid(&self) -> u32 {
+        self.oid
+    }
+
+    /// How this OID should be interpreted.
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+}
+
+/// A cache of [`PgTypeDescriptor`]s keyed by OID.
+///
+/// This plays the same caching role for dynamic type descriptors that
+/// `PgMetadataLookup` plays for resolving a single extension OID by name
+/// (see the `Hstore` type). It is filled only through
+/// [`PgTypeCache::insert`]: it performs no catalog lookups itself, and no
+/// connection creates or holds one.
+#[derive(Debug, Default)]
+pub struct PgTypeCache {
+    descriptors: RwLock<HashMap<u32, PgTypeDescriptor>>,
+}
+
+impl PgTypeCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the cached descriptor for `oid`, if one has already been
+    /// resolved.
+    pub fn get(&self, oid: u32) -> Option<PgTypeDescriptor> {
+        self.descriptors
+            .read()
+            .expect("PgTypeCache lock was poisoned")
+            .get(&oid)
+            .cloned()
+    }
+
+    /// Record the descriptor for an OID that was just resolved from the
+    /// catalog.
+    pub fn insert(&self, descriptor: PgTypeDescriptor) {
+        self.descriptors
+            .write()
+            .expect("PgTypeCache lock was poisoned")
+            .insert(descriptor.oid(), descriptor);
+    }
+}
+
+/// A `FromSql`-adjacent hook for decoding a value whose PostgreSQL type is
+/// only known at runtime.
+///
+/// Unlike [`FromSql`](crate::deserialize::FromSql), which is implemented
+/// once per compile-time `ST` marker, a type implements `FromSqlDynamic`
+/// once and then decides how to read `bytes` by matching on the
+/// [`PgTypeDescriptor`] it is given. [`PgValue`] is the motivating
+/// implementation: a single owned enum that can represent the result of
+/// any of the markers in [`super::sql_types`] without the column's type
+/// being known when the query was written.
+pub trait FromSqlDynamic: Sized {
+    /// Decode `bytes` according to the runtime type described by
+    /// `descriptor`.
+    fn from_sql_dynamic(
+        descriptor: &PgTypeDescriptor,
+        bytes: Option<&[u8]>,
+    ) -> deserialize::Result<Self>;
+}
+
+/// An owned, dynamically-typed PostgreSQL value.
+///
+/// Where every other type in this module requires the column's SQL type
+/// to be known when the query is written, `PgValue` can represent the
+/// result of a column whose type was only discovered at runtime via its
+/// [`PgTypeDescriptor`] (for instance, a `SELECT *` against a table whose
+/// schema isn't known ahead of time).
+///
+/// Array, range, and composite values are unpacked one level using their
+/// wire format (array elements and range bounds are tagged with the OID
+/// from the descriptor's `Kind`, composite fields with the OID written
+/// alongside each field), so `Array`/`Range`/`Composite` are real,
+/// populated variants rather than falling back to `Simple`. What isn't
+/// done is *recursive* decoding below that first level: a nested value is
+/// always produced as `Simple { oid, bytes }`, even if that OID is itself
+/// an array, range, or composite. Doing better would mean looking up the
+/// nested OID's own `Kind` (an array of composites, say) and recursing,
+/// which needs a catalog/cache lookup that `from_sql_dynamic` doesn't have
+/// access to today -- `FromSqlDynamic` would need to grow a
+/// `&PgTypeCache`-shaped parameter first.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgValue {
+    /// SQL `NULL`.
+    Null,
+    /// The raw bytes of a simple (non-array, non-range, non-composite)
+    /// value, along with the OID they were read under.
+    Simple { oid: u32, bytes: Vec<u8> },
+    /// The elements of an array column, each tagged with the array's
+    /// element OID.
+    Array(Vec<PgValue>),
+    /// A range column's bounds, each tagged with the range's subtype OID.
+    Range {
+        /// Whether this is the empty range (`'empty'::int4range`).
+        empty: bool,
+        /// Whether the lower bound, if present, is included in the range.
+        lower_inclusive: bool,
+        /// Whether the upper bound, if present, is included in the range.
+        upper_inclusive: bool,
+        /// The lower bound, or `None` if unbounded.
+        lower: Option<Box<PgValue>>,
+        /// The upper bound, or `None` if unbounded.
+        upper: Option<Box<PgValue>>,
+    },
+    /// The decoded fields of a composite column, in declaration order,
+    /// each tagged with its own OID as carried on the wire.
+    Composite(Vec<(String, PgValue)>),
+    /// The label of an enum column.
+    Enum(String),
+}
+
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+fn read_range_bound(bytes: &mut &[u8], oid: u32) -> deserialize::Result<Box<PgValue>> {
+    let len = bytes.read_i32::<NetworkEndian>()?;
+    let (value, rest) = bytes.split_at(len as usize);
+    *bytes = rest;
+    Ok(Box::new(PgValue::Simple {
+        oid,
+        bytes: value.to_vec(),
+    }))
+}
+
+impl FromSqlDynamic for PgValue {
+    fn from_sql_dynamic(
+        descriptor: &PgTypeDescriptor,
+        bytes: Option<&[u8]>,
+    ) -> deserialize::Result<Self> {
+        let bytes = match bytes {
+            Some(bytes) => bytes,
+            None => return Ok(PgValue::Null),
+        };
+
+        match descriptor.kind() {
+            Kind::Simple | Kind::Domain(_) => Ok(PgValue::Simple {
+                oid: descriptor.oid(),
+                bytes: bytes.to_vec(),
+            }),
+            Kind::Enum(labels) => {
+                let label = std::str::from_utf8(bytes)?;
+                if !labels.iter().any(|l| l == label) {
+                    return Err(format!(
+                        "`{}` is not a known label of the enum type with oid {}",
+                        label,
+                        descriptor.oid(),
+                    )
+                    .into());
+                }
+                Ok(PgValue::Enum(label.to_owned()))
+            }
+            Kind::Array(element_oid) => {
+                let raw = read_array(bytes)?;
+                let elements = raw
+                    .elements
+                    .into_iter()
+                    .map(|element| match element {
+                        Some(element_bytes) => PgValue::Simple {
+                            oid: *element_oid,
+                            bytes: element_bytes.to_vec(),
+                        },
+                        None => PgValue::Null,
+                    })
+                    .collect();
+                Ok(PgValue::Array(elements))
+            }
+            Kind::Range(subtype_oid) => {
+                let mut bytes = bytes;
+                let flags = bytes.read_u8()?;
+                let empty = flags & RANGE_EMPTY != 0;
+                let lower = if empty || flags & RANGE_LB_INF != 0 {
+                    None
+                } else {
+                    Some(read_range_bound(&mut bytes, *subtype_oid)?)
+                };
+                let upper = if empty || flags & RANGE_UB_INF != 0 {
+                    None
+                } else {
+                    Some(read_range_bound(&mut bytes, *subtype_oid)?)
+                };
+                Ok(PgValue::Range {
+                    empty,
+                    lower_inclusive: !empty && flags & RANGE_LB_INC != 0,
+                    upper_inclusive: !empty && flags & RANGE_UB_INC != 0,
+                    lower,
+                    upper,
+                })
+            }
+            Kind::Composite(fields) => {
+                let mut bytes = bytes;
+                let count = bytes.read_i32::<NetworkEndian>()?;
+                let mut values = Vec::with_capacity(count.max(0) as usize);
+                for _ in 0..count {
+                    let oid = bytes.read_u32::<NetworkEndian>()?;
+                    let len = bytes.read_i32::<NetworkEndian>()?;
+                    let value = if len == -1 {
+                        PgValue::Null
+                    } else {
+                        let (field_bytes, rest) = bytes.split_at(len as usize);
+                        bytes = rest;
+                        PgValue::Simple {
+                            oid,
+                            bytes: field_bytes.to_vec(),
+                        }
+                    };
+                    values.push(value);
+                }
+                let named = fields
+                    .iter()
+                    .zip(values)
+                    .map(|((name, _), value)| (name.clone(), value))
+                    .collect();
+                Ok(PgValue::Composite(named))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::byteorder::WriteBytesExt;
+    use super::*;
+
+    #[test]
+    fn cache_round_trips_a_descriptor() {
+        let cache = PgTypeCache::new();
+        assert_eq!(None, cache.get(16791));
+
+        let descriptor =
+            PgTypeDescriptor::new(16791, Kind::Enum(vec!["happy".into(), "sad".into()]));
+        cache.insert(descriptor.clone());
+
+        assert_eq!(Some(descriptor), cache.get(16791));
+    }
+
+    #[test]
+    fn dynamic_enum_rejects_unknown_labels() {
+        let descriptor =
+            PgTypeDescriptor::new(16791, Kind::Enum(vec!["happy".into(), "sad".into()]));
+        let result = PgValue::from_sql_dynamic(&descriptor, Some(b"ok"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn dynamic_enum_accepts_known_labels() {
+        let descriptor =
+            PgTypeDescriptor::new(16791, Kind::Enum(vec!["happy".into(), "sad".into()]));
+        let result = PgValue::from_sql_dynamic(&descriptor, Some(b"happy")).unwrap();
+        assert_eq!(PgValue::Enum("happy".into()), result);
+    }
+
+    #[test]
+    fn dynamic_array_decodes_its_elements() {
+        let mut bytes = Vec::new();
+        bytes.write_i32::<NetworkEndian>(1).unwrap(); // ndim
+        bytes.write_i32::<NetworkEndian>(1).unwrap(); // has_null
+        bytes.write_u32::<NetworkEndian>(23).unwrap(); // element oid (ignored; Kind's wins)
+        bytes.write_i32::<NetworkEndian>(2).unwrap(); // dimension length
+        bytes.write_i32::<NetworkEndian>(1).unwrap(); // lower bound
+        bytes.write_i32::<NetworkEndian>(4).unwrap();
+        bytes.write_i32::<NetworkEndian>(7).unwrap();
+        bytes.write_i32::<NetworkEndian>(-1).unwrap(); // NULL element
+
+        let descriptor = PgTypeDescriptor::new(1007, Kind::Array(23));
+        let result = PgValue::from_sql_dynamic(&descriptor, Some(&bytes)).unwrap();
+        assert_eq!(
+            PgValue::Array(vec![
+                PgValue::Simple {
+                    oid: 23,
+                    bytes: 7i32.to_be_bytes().to_vec(),
+                },
+                PgValue::Null,
+            ]),
+            result
+        );
+    }
+
+    #[test]
+    fn dynamic_range_decodes_present_bounds() {
+        let mut bytes = Vec::new();
+        bytes.write_u8(RANGE_LB_INC | RANGE_UB_INC).unwrap();
+        bytes.write_i32::<NetworkEndian>(4).unwrap();
+        bytes.write_i32::<NetworkEndian>(1).unwrap();
+        bytes.write_i32::<NetworkEndian>(4).unwrap();
+        bytes.write_i32::<NetworkEndian>(10).unwrap();
+
+        let descriptor = PgTypeDescriptor::new(3904, Kind::Range(23));
+        let result = PgValue::from_sql_dynamic(&descriptor, Some(&bytes)).unwrap();
+        assert_eq!(
+            PgValue::Range {
+                empty: false,
+                lower_inclusive: true,
+                upper_inclusive: true,
+                lower: Some(Box::new(PgValue::Simple {
+                    oid: 23,
+                    bytes: 1i32.to_be_bytes().to_vec(),
+                })),
+                upper: Some(Box::new(PgValue::Simple {
+                    oid: 23,
+                    bytes: 10i32.to_be_bytes().to_vec(),
+                })),
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn dynamic_range_decodes_the_empty_range() {
+        let bytes = vec![RANGE_EMPTY];
+        let descriptor = PgTypeDescriptor::new(3904, Kind::Range(23));
+        let result = PgValue::from_sql_dynamic(&descriptor, Some(&bytes)).unwrap();
+        assert_eq!(
+            PgValue::Range {
+                empty: true,
+                lower_inclusive: false,
+                upper_inclusive: false,
+                lower: None,
+                upper: None,
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn dynamic_composite_decodes_its_fields_by_position() {
+        let mut bytes = Vec::new();
+        bytes.write_i32::<NetworkEndian>(2).unwrap(); // field count
+        bytes.write_u32::<NetworkEndian>(23).unwrap();
+        bytes.write_i32::<NetworkEndian>(4).unwrap();
+        bytes.write_i32::<NetworkEndian>(1).unwrap();
+        bytes.write_u32::<NetworkEndian>(25).unwrap();
+        bytes.write_i32::<NetworkEndian>(-1).unwrap(); // NULL field
+
+        let descriptor = PgTypeDescriptor::new(
+            16800,
+            Kind::Composite(vec![("id".into(), 23), ("name".into(), 25)]),
+        );
+        let result = PgValue::from_sql_dynamic(&descriptor, Some(&bytes)).unwrap();
+        assert_eq!(
+            PgValue::Composite(vec![
+                (
+                    "id".into(),
+                    PgValue::Simple {
+                        oid: 23,
+                        bytes: 1i32.to_be_bytes().to_vec(),
+                    },
+                ),
+                ("name".into(), PgValue::Null),
+            ]),
+            result
+        );
+    }
+}
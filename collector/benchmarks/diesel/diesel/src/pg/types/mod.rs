@@ -1,13 +1,22 @@
 //! PostgreSQL specific types
 
 mod array;
+#[cfg(feature = "bit-vec")]
+mod bit_vec;
 #[doc(hidden)]
 pub mod date_and_time;
+/// Support for mapping Rust enums to user-defined PostgreSQL `ENUM` types.
+pub mod enums;
 #[doc(hidden)]
 pub mod floats;
+#[cfg(feature = "hstore")]
+mod hstore;
 mod integers;
 #[cfg(feature = "serde_json")]
 mod json;
+/// Runtime inspection of a column's actual PostgreSQL type, for schemas
+/// that cannot be known at compile time.
+pub mod kind;
 mod mac_addr;
 #[doc(hidden)]
 pub mod money;
@@ -31,14 +40,19 @@ pub mod sql_types {
     ///
     /// ### [`ToSql`] impls
     ///
-    /// - [`u32`]
+    /// - [`PgOid`]
+    /// - [`u32`] (kept for backward compatibility; prefer [`PgOid`] in new
+    ///   code, since a bare `u32` makes it easy to mix an object
+    ///   identifier up with an ordinary integer)
     ///
     /// ### [`FromSql`] impls
     ///
+    /// - [`PgOid`]
     /// - [`u32`]
     ///
     /// [`ToSql`]: ../../../serialize/trait.ToSql.html
     /// [`FromSql`]: ../../../deserialize/trait.FromSql.html
+    /// [`PgOid`]: ../../data_types/struct.PgOid.html
     /// [`u32`]: https://doc.rust-lang.org/nightly/std/primitive.u32.html
     #[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
     #[postgres(oid = "26", array_oid = "1018")]
@@ -68,20 +82,86 @@ pub mod sql_types {
     #[postgres(oid = "1184", array_oid = "1185")]
     pub struct Timestamptz;
 
+    /// The "time with time zone" SQL type, which PostgreSQL abbreviates
+    /// to `timetz`.
+    ///
+    /// ### [`ToSql`] impls
+    ///
+    /// - [`PgTimeTz`]
+    /// - `(`[`chrono::NaiveTime`]`, `[`chrono::FixedOffset`]`)` with `feature = "chrono"`
+    ///
+    /// ### [`FromSql`] impls
+    ///
+    /// - [`PgTimeTz`]
+    /// - `(`[`chrono::NaiveTime`]`, `[`chrono::FixedOffset`]`)` with `feature = "chrono"`
+    ///
+    /// [`ToSql`]: ../../../serialize/trait.ToSql.html
+    /// [`FromSql`]: ../../../deserialize/trait.FromSql.html
+    /// [`PgTimeTz`]: ../../data_types/struct.PgTimeTz.html
+    /// [`chrono::NaiveTime`]: ../../../../chrono/naive/struct.NaiveTime.html
+    /// [`chrono::FixedOffset`]: ../../../../chrono/offset/struct.FixedOffset.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # include!("../../doctest_setup.rs");
+    /// use diesel::pg::data_types::PgTimeTz;
+    ///
+    /// table! {
+    ///     events {
+    ///         id -> Integer,
+    ///         starts_at -> Timetz,
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// #     use diesel::insert_into;
+    /// #     use self::events::dsl::*;
+    /// #     let connection = connection_no_data();
+    /// #     diesel::sql_query("CREATE TABLE events (
+    /// #         id SERIAL PRIMARY KEY,
+    /// #         starts_at TIMETZ NOT NULL
+    /// #     )").execute(&connection)?;
+    /// let noon_eastern = PgTimeTz {
+    ///     microseconds_since_midnight: 12 * 60 * 60 * 1_000_000,
+    ///     utc_offset_seconds: 5 * 60 * 60,
+    /// };
+    /// let inserted = insert_into(events)
+    ///     .values(starts_at.eq(noon_eastern))
+    ///     .returning(starts_at)
+    ///     .get_result(&connection)?;
+    /// assert_eq!(noon_eastern, inserted);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+    #[postgres(oid = "1266", array_oid = "1270")]
+    pub struct Timetz;
+
     /// The `Array` SQL type.
     ///
     /// This wraps another type to represent a SQL array of that type.
-    /// Multidimensional arrays are not supported,
-    /// nor are arrays containing null.
+    /// Two-dimensional arrays are supported via `Vec<Vec<T>>`; arrays of
+    /// three or more dimensions are not. One-dimensional arrays containing
+    /// null are supported via `Array<Nullable<ST>>`; two-dimensional arrays
+    /// containing null are not.
     ///
     /// ### [`ToSql`] impls
     ///
     /// - [`Vec<T>`][Vec] for any `T` which implements `ToSql<ST>`
     /// - [`&[T]`][slice] for any `T` which implements `ToSql<ST>`
+    /// - [`Vec<Option<T>>`][Vec] for any `T` which implements `ToSql<ST>`,
+    ///   when `ST` is wrapped in `Nullable`
+    /// - [`Vec<Vec<T>>`][Vec] for any `T` which implements `ToSql<ST>`,
+    ///   representing a two-dimensional array
     ///
     /// ### [`FromSql`] impls
     ///
-    /// - [`Vec<T>`][Vec] for any `T` which implements `ToSql<ST>`
+    /// - [`Vec<T>`][Vec] for any `T` which implements `FromSql<ST>`
+    /// - [`Vec<Option<T>>`][Vec] for any `T` which implements `FromSql<ST>`,
+    ///   when `ST` is wrapped in `Nullable`
+    /// - [`Vec<Vec<T>>`][Vec] for any `T` which implements `FromSql<ST>`,
+    ///   representing a two-dimensional array
     ///
     /// [`ToSql`]: ../../../serialize/trait.ToSql.html
     /// [`FromSql`]: ../../../deserialize/trait.FromSql.html
@@ -193,6 +273,90 @@ pub mod sql_types {
     #[doc(hidden)]
     pub type Bytea = crate::sql_types::Binary;
 
+    /// The `BIT` SQL type. This type can only be used with
+    /// `feature = "bit-vec"`.
+    ///
+    /// `BIT` is a fixed-length string of bits; unlike [`Varbit`], its
+    /// length is part of the column definition and every value must match
+    /// it exactly.
+    ///
+    /// ### [`ToSql`] impls
+    ///
+    /// - [`bit_vec::BitVec`][BitVec]
+    ///
+    /// ### [`FromSql`] impls
+    ///
+    /// - [`bit_vec::BitVec`][BitVec]
+    ///
+    /// [`ToSql`]: ../../../serialize/trait.ToSql.html
+    /// [`FromSql`]: ../../../deserialize/trait.FromSql.html
+    /// [BitVec]: https://docs.rs/bit-vec/*/bit_vec/struct.BitVec.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "bit-vec")]
+    /// # include!("../../doctest_setup.rs");
+    /// # #[cfg(feature = "bit-vec")]
+    /// extern crate bit_vec;
+    /// # #[cfg(feature = "bit-vec")]
+    /// use self::bit_vec::BitVec;
+    ///
+    /// # #[cfg(feature = "bit-vec")]
+    /// table! {
+    ///     flags {
+    ///         id -> Integer,
+    ///         bits -> Bit,
+    ///     }
+    /// }
+    ///
+    /// # #[cfg(feature = "bit-vec")]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// #     use diesel::insert_into;
+    /// #     use self::flags::dsl::*;
+    /// #     let connection = connection_no_data();
+    /// #     diesel::sql_query("CREATE TABLE flags (
+    /// #         id SERIAL PRIMARY KEY,
+    /// #         bits BIT(4) NOT NULL
+    /// #     )").execute(&connection)?;
+    /// let mut inserted_bits = BitVec::from_elem(4, false);
+    /// inserted_bits.set(1, true);
+    /// let selected_bits = insert_into(flags)
+    ///     .values(bits.eq(&inserted_bits))
+    ///     .returning(bits)
+    ///     .get_result::<BitVec>(&connection)?;
+    /// assert_eq!(inserted_bits, selected_bits);
+    /// #     Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "bit-vec"))]
+    /// # fn main() {}
+    /// ```
+    #[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+    #[postgres(oid = "1560", array_oid = "1561")]
+    pub struct Bit;
+
+    /// The `VARBIT` (a.k.a. `BIT VARYING`) SQL type. This type can only be
+    /// used with `feature = "bit-vec"`.
+    ///
+    /// ### [`ToSql`] impls
+    ///
+    /// - [`bit_vec::BitVec`][BitVec]
+    ///
+    /// ### [`FromSql`] impls
+    ///
+    /// - [`bit_vec::BitVec`][BitVec]
+    ///
+    /// [`ToSql`]: ../../../serialize/trait.ToSql.html
+    /// [`FromSql`]: ../../../deserialize/trait.FromSql.html
+    /// [BitVec]: https://docs.rs/bit-vec/*/bit_vec/struct.BitVec.html
+    #[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+    #[postgres(oid = "1562", array_oid = "1563")]
+    pub struct Varbit;
+
+    /// Alias for `Varbit`.
+    #[doc(hidden)]
+    pub type Varbyte = Varbit;
+
     #[doc(hidden)]
     pub type Bpchar = crate::sql_types::VarChar;
 
@@ -273,6 +437,77 @@ pub mod sql_types {
     #[postgres(oid = "3802", array_oid = "3807")]
     pub struct Jsonb;
 
+    /// The `hstore` SQL type. This type can only be used with
+    /// `feature = "hstore"`.
+    ///
+    /// Unlike the other types in this module, `hstore` is shipped as a
+    /// PostgreSQL extension (`CREATE EXTENSION hstore;`) rather than a
+    /// built-in type, so its OID is assigned per-database instead of being
+    /// fixed across installations. Because of that, `Hstore` is declared
+    /// with `#[postgres(type_name = "hstore")]` rather than a hard-coded
+    /// `oid`/`array_oid`, and the real OID is looked up by name the first
+    /// time the connection needs it.
+    ///
+    /// ### [`ToSql`] impls
+    ///
+    /// - [`HashMap<String, Option<String>>`][HashMap]
+    /// - [`BTreeMap<String, Option<String>>`][BTreeMap]
+    ///
+    /// ### [`FromSql`] impls
+    ///
+    /// - [`HashMap<String, Option<String>>`][HashMap]
+    /// - [`BTreeMap<String, Option<String>>`][BTreeMap]
+    ///
+    /// [`ToSql`]: ../../../serialize/trait.ToSql.html
+    /// [`FromSql`]: ../../../deserialize/trait.FromSql.html
+    /// [HashMap]: https://doc.rust-lang.org/nightly/std/collections/struct.HashMap.html
+    /// [BTreeMap]: https://doc.rust-lang.org/nightly/std/collections/struct.BTreeMap.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![allow(dead_code)]
+    /// # #[cfg(feature = "hstore")]
+    /// # include!("../../doctest_setup.rs");
+    /// # #[cfg(feature = "hstore")]
+    /// table! {
+    ///     contacts {
+    ///         id -> Integer,
+    ///         name -> VarChar,
+    ///         attributes -> Hstore,
+    ///     }
+    /// }
+    ///
+    /// # #[cfg(feature = "hstore")]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// #     use diesel::insert_into;
+    /// #     use self::contacts::dsl::*;
+    /// #     use std::collections::HashMap;
+    /// #     let connection = connection_no_data();
+    /// #     diesel::sql_query("CREATE EXTENSION IF NOT EXISTS hstore").execute(&connection)?;
+    /// #     diesel::sql_query("CREATE TABLE contacts (
+    /// #         id SERIAL PRIMARY KEY,
+    /// #         name VARCHAR NOT NULL,
+    /// #         attributes HSTORE NOT NULL
+    /// #     )").execute(&connection)?;
+    /// let mut santas_attributes = HashMap::new();
+    /// santas_attributes.insert("home".to_string(), Some("North Pole".to_string()));
+    /// santas_attributes.insert("pets".to_string(), None);
+    /// let inserted_attributes = insert_into(contacts)
+    ///     .values((name.eq("Claus"), attributes.eq(&santas_attributes)))
+    ///     .returning(attributes)
+    ///     .get_result::<HashMap<String, Option<String>>>(&connection)?;
+    /// assert_eq!(santas_attributes, inserted_attributes);
+    /// #     Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "hstore"))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(feature = "hstore")]
+    #[derive(Debug, Clone, Copy, Default, QueryId, SqlType)]
+    #[postgres(type_name = "hstore")]
+    pub struct Hstore;
+
     /// The PostgreSQL [Money](https://www.postgresql.org/docs/9.1/static/datatype-money.html) type.
     ///
     /// ### [`ToSql`] impls
@@ -490,6 +725,16 @@ mod ops {
         type Output = Timestamptz;
     }
 
+    impl Add for Timetz {
+        type Rhs = Interval;
+        type Output = Timetz;
+    }
+
+    impl Sub for Timetz {
+        type Rhs = Interval;
+        type Output = Timetz;
+    }
+
     impl Add for Cidr {
         type Rhs = Bigint;
         type Output = Inet;